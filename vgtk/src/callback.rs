@@ -0,0 +1,85 @@
+use std::rc::Rc;
+
+/// A type-safe handle a child component can use to notify its parent of an
+/// event, without either side knowing the other's concrete `Component` or
+/// `Message` type.
+///
+/// Built with [`Scope::callback`](crate::scope::Scope::callback), which
+/// closes over the parent's scope and a mapping closure from the payload
+/// type `T` to the parent's message type. Calling
+/// [`emit`](Callback::emit) posts the mapped message straight into the
+/// parent's `update`, the same way [`Scope::send_message`](crate::scope::Scope::send_message)
+/// does.
+pub struct Callback<T> {
+    emit: Rc<dyn Fn(T)>,
+}
+
+impl<T> Clone for Callback<T> {
+    fn clone(&self) -> Self {
+        Callback {
+            emit: self.emit.clone(),
+        }
+    }
+}
+
+impl<T, F> From<F> for Callback<T>
+where
+    F: 'static + Fn(T),
+{
+    fn from(f: F) -> Self {
+        Callback { emit: Rc::new(f) }
+    }
+}
+
+impl<T> Default for Callback<T> {
+    /// A no-op callback, so a `Properties` struct with a bare `Callback<T>`
+    /// field (rather than `Option<Callback<T>>`) can still derive `Default`
+    /// as `Component::Properties` requires.
+    fn default() -> Self {
+        Callback { emit: Rc::new(|_| {}) }
+    }
+}
+
+impl<T> Callback<T> {
+    /// Invoke the callback with `value`.
+    pub fn emit(&self, value: T) {
+        (self.emit)(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn emit_invokes_the_closure() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let callback = {
+            let seen = seen.clone();
+            Callback::from(move |value: i32| seen.borrow_mut().push(value))
+        };
+        callback.emit(1);
+        callback.emit(2);
+        assert_eq!(*seen.borrow(), vec![1, 2]);
+    }
+
+    #[test]
+    fn clone_shares_the_same_closure() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let callback = {
+            let seen = seen.clone();
+            Callback::from(move |value: i32| seen.borrow_mut().push(value))
+        };
+        let cloned = callback.clone();
+        cloned.emit(3);
+        assert_eq!(*seen.borrow(), vec![3]);
+    }
+
+    #[test]
+    fn default_is_a_no_op() {
+        let callback: Callback<i32> = Default::default();
+        callback.emit(1);
+        callback.emit(2);
+    }
+}
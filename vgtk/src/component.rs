@@ -5,14 +5,23 @@ use glib::futures::{
     Future, Poll, StreamExt,
 };
 use glib::{ObjectExt, WeakRef};
-use gtk::{Container, Widget};
+use gtk::{Container, ContainerExt, Widget};
 
 use std::any::TypeId;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::pin::Pin;
+use std::rc::Rc;
 use std::sync::RwLock;
 
+use glib::futures::future::{AbortHandle, Abortable};
+
+use crate::mailbox::OverflowPolicy;
 use crate::scope::{AnyScope, Scope};
+use crate::signal::{self, EffectId};
+use crate::subscription::Subscription;
 use crate::vdom::State;
 use crate::vnode::VNode;
 
@@ -35,6 +44,32 @@ pub trait Component: Default + Unpin {
 
     fn unmounted(&mut self) {}
 
+    /// How many messages this component's mailbox can hold before
+    /// [`mailbox_overflow`](Component::mailbox_overflow) kicks in. Defaults
+    /// to 64, generous enough for ordinary event handlers while still
+    /// bounding a runaway emitter.
+    fn mailbox_capacity() -> usize {
+        64
+    }
+
+    /// What to do when this component's mailbox is full. Defaults to
+    /// [`OverflowPolicy::Block`], which never drops a message but makes
+    /// `send`-ing callers wait for room.
+    fn mailbox_overflow() -> OverflowPolicy {
+        OverflowPolicy::Block
+    }
+
+    /// Declare the external event streams this component wants to listen to
+    /// while it's mounted.
+    ///
+    /// Called after every render; [`ComponentTask`] diffs the returned list
+    /// against the subscriptions it already has running (by
+    /// [`Subscription`] id), starting newly-declared ones and dropping ones
+    /// that are no longer present.
+    fn subscriptions(&self) -> Vec<Subscription<Self::Message>> {
+        Vec::new()
+    }
+
     fn view(&self) -> VNode<Self>;
 }
 
@@ -74,6 +109,14 @@ where
     state: C,
     ui_state: State<C>,
     channel: Pin<Box<dyn Stream<Item = ComponentMessage<C>>>>,
+    tasks: Rc<RefCell<Vec<(u64, AbortHandle)>>>,
+    subscriptions: HashMap<Cow<'static, str>, AbortHandle>,
+    render_effect: EffectId,
+    parent: Option<WeakRef<Container>>,
+    /// Set whenever a failed patch rebuilds `ui_state` from scratch, so the
+    /// next [`take_new_root_widget`](ComponentTask::take_new_root_widget)
+    /// call can tell the caller the widget identity changed.
+    root_replaced: bool,
 }
 
 impl<C, P> ComponentTask<C, P>
@@ -87,7 +130,8 @@ where
         parent_scope: Option<&Scope<P>>,
     ) -> (Scope<C>, UnboundedSender<ComponentMessage<C>>, Self) {
         let (sys_send, sys_recv) = unbounded();
-        let (user_send, user_recv) = unbounded();
+        let (user_send, user_recv) =
+            crate::mailbox::mailbox(C::mailbox_capacity(), C::mailbox_overflow());
 
         // As `C::Message` must be `Send` but `C::Properties` can't be,
         // we keep two senders but merge them into a single receiver at
@@ -103,19 +147,54 @@ where
             None => Scope::new(type_name, user_send),
         };
         let state = C::create(props);
-        let initial_view = state.view();
+        let render_effect = EffectId::next();
+        let initial_view = signal::track(render_effect, || state.view());
         let ui_state = State::build(&initial_view, parent, &scope);
-        (
-            scope.clone(),
-            sys_send,
-            ComponentTask {
-                scope,
-                parent_scope: parent_scope.cloned(),
-                state,
-                ui_state,
-                channel,
-            },
-        )
+        let tasks = scope.tasks();
+        let mut task = ComponentTask {
+            scope,
+            parent_scope: parent_scope.cloned(),
+            state,
+            ui_state,
+            channel,
+            tasks,
+            subscriptions: HashMap::new(),
+            render_effect,
+            parent: parent.map(|container| container.downgrade()),
+            root_replaced: false,
+        };
+        task.sync_subscriptions();
+        (task.scope.clone(), sys_send, task)
+    }
+
+    /// Diff `self.state.subscriptions()` against the subscriptions currently
+    /// running, starting streams that are newly declared and aborting ones
+    /// that have disappeared.
+    fn sync_subscriptions(&mut self) {
+        let declared = self.state.subscriptions();
+        let mut live = HashSet::with_capacity(declared.len());
+        for subscription in declared {
+            live.insert(subscription.id.clone());
+            if self.subscriptions.contains_key(&subscription.id) {
+                continue;
+            }
+            let scope = self.scope.clone();
+            let (handle, registration) = AbortHandle::new_pair();
+            let mut stream = Abortable::new(subscription.stream, registration);
+            glib::MainContext::ref_thread_default().spawn_local(async move {
+                while let Some(msg) = stream.next().await {
+                    scope.send_message(msg);
+                }
+            });
+            self.subscriptions.insert(subscription.id, handle);
+        }
+        self.subscriptions.retain(|id, handle| {
+            let keep = live.contains(id);
+            if !keep {
+                handle.abort();
+            }
+            keep
+        });
     }
 
     pub fn process(&mut self, ctx: &mut Context) -> Poll<()> {
@@ -137,17 +216,51 @@ where
                         self.state.mounted();
                     }
                     ComponentMessage::Unmounted => {
+                        // Stop any in-flight `spawn`/`spawn_stream` tasks, and
+                        // every declared `Subscription`, so they don't keep
+                        // running (and posting into this now-dead channel)
+                        // after the component itself is gone.
+                        for (_, handle) in self.tasks.borrow_mut().drain(..) {
+                            handle.abort();
+                        }
+                        for (_, handle) in self.subscriptions.drain() {
+                            handle.abort();
+                        }
+                        // Likewise tear down this component's render effect,
+                        // so it doesn't keep a dead `Waker` and subscriber
+                        // entries alive in every `Signal` it ever read.
+                        signal::drop_effect(self.render_effect);
                         self.state.unmounted();
                     }
                 },
-                Poll::Pending if render => {
+                Poll::Pending if render || signal::take_dirty(self.render_effect) => {
                     // we patch
-                    let new_view = self.state.view();
+                    let new_view = signal::track(self.render_effect, || self.state.view());
                     self.scope.mute();
                     if !self.ui_state.patch(&new_view, None, &self.scope) {
-                        unimplemented!("don't know how to propagate failed patch");
+                        // The new view's root widget is a different type than
+                        // the one we already have built (e.g. an `if`/`match`
+                        // at the top of `view()` switched branches) and can't
+                        // be reconciled in place. Detach the stale widget from
+                        // its parent and rebuild the subtree from scratch,
+                        // re-parenting it where the old one lived.
+                        //
+                        // A root-mounted task (no `Container` parent — the
+                        // component is attached straight to e.g. a `Window`)
+                        // has nothing to detach from or reinsert into here;
+                        // `root_replaced` tells the owner of that attachment
+                        // to fetch the new widget via `take_new_root_widget`
+                        // and re-attach it themselves, instead of the old
+                        // widget silently being left on screen.
+                        let parent = self.parent.as_ref().and_then(WeakRef::upgrade);
+                        if let Some(parent) = &parent {
+                            parent.remove(self.ui_state.object());
+                        }
+                        self.ui_state = State::build(&new_view, parent.as_ref(), &self.scope);
+                        self.root_replaced = true;
                     }
                     self.scope.unmute();
+                    self.sync_subscriptions();
                     return Poll::Pending;
                 }
                 Poll::Ready(None) => {
@@ -163,6 +276,37 @@ where
         self.ui_state.object().clone()
     }
 
+    /// If a failed patch rebuilt the root widget since the last call,
+    /// returns it so the caller can re-attach it wherever this task's
+    /// widget lives (a parent `Container` re-parents itself automatically;
+    /// a root-mounted task attached directly to e.g. a `Window` does not,
+    /// and the caller must pull the new widget from here and swap it in).
+    /// Returns `None` if the root widget hasn't changed.
+    pub fn take_new_root_widget(&mut self) -> Option<Widget> {
+        if self.root_replaced {
+            self.root_replaced = false;
+            Some(self.widget())
+        } else {
+            None
+        }
+    }
+
+    /// Headless snapshot-testing entry point: drive `props` through
+    /// `update`/`view` for each of `messages` with no GTK widgets built at
+    /// all, returning the rendered [`Tree`](crate::testing::Tree) after the
+    /// initial render and after each message that triggered a re-render.
+    /// See [`crate::testing`].
+    pub fn render_to_tree(
+        props: C::Properties,
+        messages: Vec<C::Message>,
+    ) -> Vec<crate::testing::Tree> {
+        crate::testing::render::<C>(props, messages)
+    }
+
+    /// Fetch the parent scope of the task currently being polled from
+    /// thread-local task context, downcast to `Scope<C>`. Prefer [`Scope::callback`] for child-to-parent notification;
+    /// this panicky downcast exists for callers that need the scope itself
+    /// rather than a typed callback into it.
     pub(crate) fn current_parent_scope() -> Scope<C> {
         LOCAL_CONTEXT.with(|key| {
             let lock = key.read().unwrap();
@@ -207,6 +351,10 @@ where
     type Output = ();
 
     fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Self::Output> {
+        // Keep this task's waker current so a `Signal::set` from anywhere
+        // (not just from a message arriving on our own channel) can wake us
+        // up for a re-render; see `signal::mark_dirty`.
+        signal::set_waker(self.render_effect, ctx.waker().clone());
         LOCAL_CONTEXT.with(|key| {
             *key.write().unwrap() = LocalContext {
                 parent_scope: self.parent_scope.as_ref().map(|scope| scope.clone().into()),
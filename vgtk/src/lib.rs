@@ -0,0 +1,9 @@
+pub mod callback;
+pub mod component;
+pub mod mailbox;
+pub mod scope;
+pub mod signal;
+pub mod subscription;
+pub mod testing;
+pub mod vdom;
+pub mod vnode;
@@ -0,0 +1,239 @@
+use std::collections::VecDeque;
+use std::cell::RefCell;
+use std::fmt;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+use glib::futures::{Future, Stream};
+
+/// What a component's mailbox should do when it's full and another message
+/// arrives, so chatty emitters (a high-frequency input, a polling
+/// subscription) can shed load instead of growing without bound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Wait for room. [`MailboxSender::try_send`] fails with
+    /// [`MailboxFull`] until a slot frees up; [`MailboxSender::send`]
+    /// suspends instead.
+    Block,
+    /// Make room by discarding the oldest queued message.
+    DropOldest,
+    /// Discard the incoming message, keeping what's already queued.
+    DropNewest,
+}
+
+struct Shared<T> {
+    queue: VecDeque<T>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    closed: bool,
+    recv_waker: Option<Waker>,
+    send_wakers: Vec<Waker>,
+}
+
+/// The sending half of a bounded, single-threaded component mailbox.
+pub struct MailboxSender<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+impl<T> Clone for MailboxSender<T> {
+    fn clone(&self) -> Self {
+        MailboxSender {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+/// The receiving half of a bounded, single-threaded component mailbox.
+pub struct MailboxReceiver<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+/// Returned by [`MailboxSender::try_send`] when the mailbox is full under
+/// [`OverflowPolicy::Block`]. Carries the value back so the caller can
+/// retry it (e.g. via [`MailboxSender::send`]) instead of losing it.
+#[derive(Debug)]
+pub struct MailboxFull<T>(pub T);
+
+impl<T> fmt::Display for MailboxFull<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "mailbox is full")
+    }
+}
+
+/// Create a bounded mailbox with room for `capacity` messages, applying
+/// `policy` once it's full.
+pub fn mailbox<T>(capacity: usize, policy: OverflowPolicy) -> (MailboxSender<T>, MailboxReceiver<T>) {
+    let shared = Rc::new(RefCell::new(Shared {
+        queue: VecDeque::with_capacity(capacity),
+        capacity,
+        policy,
+        closed: false,
+        recv_waker: None,
+        send_wakers: Vec::new(),
+    }));
+    (
+        MailboxSender {
+            shared: shared.clone(),
+        },
+        MailboxReceiver { shared },
+    )
+}
+
+impl<T> MailboxSender<T> {
+    /// Enqueue `value` without waiting, applying this mailbox's
+    /// [`OverflowPolicy`] if it's already full.
+    pub fn try_send(&self, value: T) -> Result<(), MailboxFull<T>> {
+        let mut shared = self.shared.borrow_mut();
+        if shared.queue.len() >= shared.capacity {
+            match shared.policy {
+                OverflowPolicy::Block => return Err(MailboxFull(value)),
+                OverflowPolicy::DropNewest => return Ok(()),
+                OverflowPolicy::DropOldest => {
+                    shared.queue.pop_front();
+                }
+            }
+        }
+        shared.queue.push_back(value);
+        if let Some(waker) = shared.recv_waker.take() {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    /// Enqueue `value`, suspending under [`OverflowPolicy::Block`] until
+    /// there's room (other policies never need to wait).
+    pub fn send(&self, value: T) -> MailboxSend<T> {
+        MailboxSend {
+            shared: self.shared.clone(),
+            value: Some(value),
+        }
+    }
+}
+
+impl<T> Drop for MailboxSender<T> {
+    fn drop(&mut self) {
+        // Rc::strong_count also sees the receiver's clone of `shared`, so
+        // this is just a best-effort nudge to wake a pending receiver once
+        // the last sender goes away; it's harmless if it fires early.
+        if Rc::strong_count(&self.shared) <= 2 {
+            let mut shared = self.shared.borrow_mut();
+            shared.closed = true;
+            if let Some(waker) = shared.recv_waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+#[must_use = "futures do nothing unless polled"]
+pub struct MailboxSend<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+    value: Option<T>,
+}
+
+impl<T> Future for MailboxSend<T> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<()> {
+        let this = self.get_mut();
+        let value = this.value.take().expect("MailboxSend polled after completion");
+        let mut shared = this.shared.borrow_mut();
+        if shared.queue.len() >= shared.capacity && shared.policy == OverflowPolicy::Block {
+            shared.send_wakers.push(ctx.waker().clone());
+            this.value = Some(value);
+            return Poll::Pending;
+        }
+        drop(shared);
+        let sender = MailboxSender {
+            shared: this.shared.clone(),
+        };
+        let _ = sender.try_send(value);
+        Poll::Ready(())
+    }
+}
+
+impl<T> Stream for MailboxReceiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context) -> Poll<Option<T>> {
+        let mut shared = self.shared.borrow_mut();
+        if let Some(value) = shared.queue.pop_front() {
+            let wakers = std::mem::take(&mut shared.send_wakers);
+            drop(shared);
+            for waker in wakers {
+                waker.wake();
+            }
+            Poll::Ready(Some(value))
+        } else if shared.closed {
+            Poll::Ready(None)
+        } else {
+            shared.recv_waker = Some(ctx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::task::{RawWaker, RawWakerVTable};
+
+    fn noop_waker() -> Waker {
+        fn clone(_: *const ()) -> RawWaker {
+            raw_waker()
+        }
+        fn noop(_: *const ()) {}
+        fn raw_waker() -> RawWaker {
+            static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+            RawWaker::new(std::ptr::null(), &VTABLE)
+        }
+        unsafe { Waker::from_raw(raw_waker()) }
+    }
+
+    fn poll_next<T>(receiver: &mut MailboxReceiver<T>) -> Poll<Option<T>> {
+        let waker = noop_waker();
+        let mut ctx = Context::from_waker(&waker);
+        Pin::new(receiver).poll_next(&mut ctx)
+    }
+
+    #[test]
+    fn block_rejects_once_full() {
+        let (sender, mut receiver) = mailbox(2, OverflowPolicy::Block);
+        sender.try_send(1).unwrap();
+        sender.try_send(2).unwrap();
+        match sender.try_send(3) {
+            Err(MailboxFull(3)) => {}
+            other => panic!("expected MailboxFull(3), got {:?}", other),
+        }
+        assert_eq!(poll_next(&mut receiver), Poll::Ready(Some(1)));
+        assert_eq!(poll_next(&mut receiver), Poll::Ready(Some(2)));
+    }
+
+    #[test]
+    fn drop_oldest_evicts_front() {
+        let (sender, mut receiver) = mailbox(2, OverflowPolicy::DropOldest);
+        sender.try_send(1).unwrap();
+        sender.try_send(2).unwrap();
+        sender.try_send(3).unwrap();
+        assert_eq!(poll_next(&mut receiver), Poll::Ready(Some(2)));
+        assert_eq!(poll_next(&mut receiver), Poll::Ready(Some(3)));
+    }
+
+    #[test]
+    fn drop_newest_discards_incoming() {
+        let (sender, mut receiver) = mailbox(2, OverflowPolicy::DropNewest);
+        sender.try_send(1).unwrap();
+        sender.try_send(2).unwrap();
+        sender.try_send(3).unwrap();
+        assert_eq!(poll_next(&mut receiver), Poll::Ready(Some(1)));
+        assert_eq!(poll_next(&mut receiver), Poll::Ready(Some(2)));
+    }
+
+    #[test]
+    fn closes_once_all_senders_dropped() {
+        let (sender, mut receiver) = mailbox::<u32>(2, OverflowPolicy::Block);
+        drop(sender);
+        assert_eq!(poll_next(&mut receiver), Poll::Ready(None));
+    }
+}
@@ -0,0 +1,205 @@
+use glib::futures::future::{AbortHandle, Abortable};
+use glib::futures::{Future, Stream, StreamExt};
+use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use crate::callback::Callback;
+use crate::component::Component;
+use crate::mailbox::{MailboxFull, MailboxSend, MailboxSender};
+
+/// Uniquely identifies a handle pushed onto a [`Scope`]'s `tasks` list, so a
+/// completed `spawn`/`spawn_stream` future can find and remove its own entry
+/// instead of only ever being cleared in bulk on unmount.
+fn next_task_id() -> u64 {
+    thread_local! {
+        static NEXT: Cell<u64> = Cell::new(0);
+    }
+    NEXT.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        id
+    })
+}
+
+/// A handle to a running [`ComponentTask`](crate::component::ComponentTask),
+/// used to post messages into it from the outside (event handlers, parent
+/// components, async tasks) without owning the task itself.
+pub struct Scope<C: Component> {
+    name: &'static str,
+    sender: MailboxSender<C::Message>,
+    muted: Rc<Cell<bool>>,
+    tasks: Rc<RefCell<Vec<(u64, AbortHandle)>>>,
+}
+
+impl<C: Component> Clone for Scope<C> {
+    fn clone(&self) -> Self {
+        Scope {
+            name: self.name,
+            sender: self.sender.clone(),
+            muted: self.muted.clone(),
+            tasks: self.tasks.clone(),
+        }
+    }
+}
+
+impl<C: 'static + Component> Scope<C> {
+    pub(crate) fn new(name: &'static str, sender: MailboxSender<C::Message>) -> Self {
+        Scope {
+            name,
+            sender,
+            muted: Rc::new(Cell::new(false)),
+            tasks: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    pub(crate) fn inherit<P: Component>(
+        &self,
+        name: &'static str,
+        sender: MailboxSender<C::Message>,
+    ) -> Scope<C> {
+        Scope::new(name, sender)
+    }
+
+    /// The abort handles for every future/stream currently spawned through
+    /// this scope, shared with the owning
+    /// [`ComponentTask`](crate::component::ComponentTask) so it can cancel
+    /// them on unmount.
+    pub(crate) fn tasks(&self) -> Rc<RefCell<Vec<(u64, AbortHandle)>>> {
+        self.tasks.clone()
+    }
+
+    /// Spawn a future that resolves to a single `C::Message`, delivering it
+    /// to this component's `update` once it completes.
+    ///
+    /// The future is tied to the component's lifetime: if the component is
+    /// unmounted before the future resolves, it is aborted rather than left
+    /// to post into a dead channel. Its entry in `tasks` is also reclaimed
+    /// once the future completes on its own, so a component that spawns
+    /// repeatedly while mounted (e.g. a polling loop) doesn't accumulate one
+    /// dead handle per spawn for its whole lifetime.
+    pub fn spawn<F>(&self, future: F)
+    where
+        F: 'static + Future<Output = C::Message>,
+    {
+        let scope = self.clone();
+        let (handle, registration) = AbortHandle::new_pair();
+        let id = next_task_id();
+        self.tasks.borrow_mut().push((id, handle));
+        let tasks = self.tasks.clone();
+        let future = Abortable::new(future, registration);
+        glib::MainContext::ref_thread_default().spawn_local(async move {
+            if let Ok(msg) = future.await {
+                scope.send_message(msg);
+            }
+            tasks.borrow_mut().retain(|(task_id, _)| *task_id != id);
+        });
+    }
+
+    /// Spawn a stream, delivering each `C::Message` it yields to this
+    /// component's `update` as it arrives.
+    ///
+    /// Like [`spawn`](Scope::spawn), the stream is aborted on unmount, and
+    /// its `tasks` entry is reclaimed once the stream ends on its own.
+    pub fn spawn_stream<S>(&self, stream: S)
+    where
+        S: 'static + Stream<Item = C::Message>,
+    {
+        let scope = self.clone();
+        let (handle, registration) = AbortHandle::new_pair();
+        let id = next_task_id();
+        self.tasks.borrow_mut().push((id, handle));
+        let tasks = self.tasks.clone();
+        let mut stream = Abortable::new(stream, registration);
+        glib::MainContext::ref_thread_default().spawn_local(async move {
+            while let Some(msg) = stream.next().await {
+                scope.send_message(msg);
+            }
+            tasks.borrow_mut().retain(|(task_id, _)| *task_id != id);
+        });
+    }
+
+    pub(crate) fn mute(&self) {
+        self.muted.set(true);
+    }
+
+    pub(crate) fn unmute(&self) {
+        self.muted.set(false);
+    }
+
+    pub(crate) fn is_muted(&self) -> bool {
+        self.muted.get()
+    }
+
+    /// Enqueue a message into this component's mailbox without waiting,
+    /// failing with [`MailboxFull`] if it's full under
+    /// [`OverflowPolicy::Block`] (the other policies never fail here).
+    pub fn try_send(&self, msg: C::Message) -> Result<(), MailboxFull<C::Message>> {
+        self.sender.try_send(msg)
+    }
+
+    /// Enqueue a message into this component's mailbox, suspending under
+    /// [`OverflowPolicy::Block`] until there's room.
+    pub fn send(&self, msg: C::Message) -> MailboxSend<C::Message> {
+        self.sender.send(msg)
+    }
+
+    /// Post a message to this component's `update`, as if it had produced
+    /// it from within `update` itself.
+    ///
+    /// Under [`OverflowPolicy::Block`] this doesn't actually block the
+    /// caller: if the mailbox is momentarily full, the message is handed to
+    /// a background task that delivers it once room frees up, so existing
+    /// fire-and-forget callers (spawned futures, subscriptions, callbacks)
+    /// don't need to become `async` themselves.
+    pub fn send_message(&self, msg: C::Message) {
+        if let Err(MailboxFull(msg)) = self.sender.try_send(msg) {
+            glib::MainContext::ref_thread_default().spawn_local(self.sender.send(msg));
+        }
+    }
+
+    /// Build a [`Callback<T>`](Callback) that a child component can invoke
+    /// with a `T` payload to have `map` turn it into one of this
+    /// component's messages and post it into `update`.
+    ///
+    /// This is the type-safe alternative to reaching for
+    /// [`AnyScope::try_get`] from a child: the parent hands its children a
+    /// `Callback` as a property, and neither side needs to know the other's
+    /// concrete `Component` type.
+    pub fn callback<T, F>(&self, map: F) -> Callback<T>
+    where
+        T: 'static,
+        F: 'static + Fn(T) -> C::Message,
+    {
+        let scope = self.clone();
+        Callback::from(move |payload| scope.send_message(map(payload)))
+    }
+}
+
+/// A type-erased [`Scope`], used to thread a parent's scope down to its
+/// children without the child needing to know the parent's concrete
+/// `Component` type.
+#[derive(Clone)]
+pub struct AnyScope {
+    type_id: TypeId,
+    scope: Rc<dyn Any>,
+}
+
+impl<C: 'static + Component> From<Scope<C>> for AnyScope {
+    fn from(scope: Scope<C>) -> Self {
+        AnyScope {
+            type_id: TypeId::of::<C>(),
+            scope: Rc::new(scope),
+        }
+    }
+}
+
+impl AnyScope {
+    pub(crate) fn try_get<C: 'static + Component>(&self) -> Option<Scope<C>> {
+        if self.type_id == TypeId::of::<C>() {
+            self.scope.downcast_ref::<Scope<C>>().cloned()
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::task::Waker;
+
+/// Identifies a render effect — one per mounted component, whether or not
+/// it uses [`Signal`], used to track which signals its `view()` reads and to notice
+/// when a write should trigger a re-render without `update` having to
+/// return `true`.
+///
+/// There is exactly one effect per component, covering its whole `view()`;
+/// this buys "skip re-rendering components that don't touch the signal"
+/// rather than true per-subtree patching, which would need `VNode`s to
+/// carry stable per-node identity that this tree doesn't have yet.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct EffectId(u64);
+
+impl EffectId {
+    pub fn next() -> Self {
+        thread_local! {
+            static NEXT: Cell<u64> = Cell::new(0);
+        }
+        NEXT.with(|next| {
+            let id = next.get();
+            next.set(id + 1);
+            EffectId(id)
+        })
+    }
+}
+
+thread_local! {
+    static EFFECT_STACK: RefCell<Vec<EffectId>> = RefCell::new(Vec::new());
+    static DEPENDENCIES: RefCell<HashMap<EffectId, Vec<Rc<RefCell<HashSet<EffectId>>>>>> =
+        RefCell::new(HashMap::new());
+    static DIRTY: RefCell<HashSet<EffectId>> = RefCell::new(HashSet::new());
+    static WAKERS: RefCell<HashMap<EffectId, Waker>> = RefCell::new(HashMap::new());
+}
+
+/// Register the `Waker` that should be woken when `effect` is next marked
+/// dirty. [`ComponentTask::poll`](crate::component::ComponentTask) calls
+/// this on every poll with its current task waker, so a `Signal::set` from
+/// anywhere — including from outside this task's own channel, like a
+/// `glib::timeout_add` callback — actually schedules a re-poll instead of
+/// sitting dirty until something unrelated happens to wake the task.
+pub fn set_waker(effect: EffectId, waker: Waker) {
+    WAKERS.with(|wakers| wakers.borrow_mut().insert(effect, waker));
+}
+
+/// Run `f` (typically a component's `view()`) with `effect` registered as
+/// the currently-tracking effect, so any [`Signal::get`] call inside
+/// subscribes it.
+///
+/// `effect`'s subscriptions from its previous run are dropped first, so a
+/// signal it stopped reading (e.g. behind a branch that's no longer taken)
+/// doesn't keep notifying it forever.
+pub fn track<R>(effect: EffectId, f: impl FnOnce() -> R) -> R {
+    if let Some(deps) = DEPENDENCIES.with(|deps| deps.borrow_mut().remove(&effect)) {
+        for subscribers in deps {
+            subscribers.borrow_mut().remove(&effect);
+        }
+    }
+    EFFECT_STACK.with(|stack| stack.borrow_mut().push(effect));
+    let result = f();
+    EFFECT_STACK.with(|stack| stack.borrow_mut().pop());
+    result
+}
+
+fn current_effect() -> Option<EffectId> {
+    EFFECT_STACK.with(|stack| stack.borrow().last().copied())
+}
+
+fn subscribe(effect: EffectId, subscribers: Rc<RefCell<HashSet<EffectId>>>) {
+    subscribers.borrow_mut().insert(effect);
+    DEPENDENCIES.with(|deps| deps.borrow_mut().entry(effect).or_insert_with(Vec::new).push(subscribers));
+}
+
+fn mark_dirty(effect: EffectId) {
+    DIRTY.with(|dirty| dirty.borrow_mut().insert(effect));
+    WAKERS.with(|wakers| {
+        if let Some(waker) = wakers.borrow().get(&effect) {
+            waker.wake_by_ref();
+        }
+    });
+}
+
+/// Check whether `effect` has been marked dirty by a signal write since the
+/// last call, clearing the mark.
+pub fn take_dirty(effect: EffectId) -> bool {
+    DIRTY.with(|dirty| dirty.borrow_mut().remove(&effect))
+}
+
+/// Tear down everything tracked for `effect`: its entry in `DEPENDENCIES`
+/// (unsubscribing it from every [`Signal`] it read), its `DIRTY` mark if
+/// any, and its registered `Waker`.
+///
+/// Called from [`ComponentTask`](crate::component::ComponentTask)'s
+/// `Unmounted` handling, the same place spawned tasks and subscriptions get
+/// aborted, so an unmounted component's effect doesn't linger forever in
+/// these thread-local maps or in the subscriber set of any signal it read.
+pub fn drop_effect(effect: EffectId) {
+    if let Some(deps) = DEPENDENCIES.with(|deps| deps.borrow_mut().remove(&effect)) {
+        for subscribers in deps {
+            subscribers.borrow_mut().remove(&effect);
+        }
+    }
+    DIRTY.with(|dirty| dirty.borrow_mut().remove(&effect));
+    WAKERS.with(|wakers| wakers.borrow_mut().remove(&effect));
+}
+
+/// A reactive value. Reading it with [`Signal::get`] inside a [`track`]ed
+/// render effect subscribes that effect; writing it with [`Signal::set`]
+/// marks every subscribed effect dirty and wakes its task, so
+/// [`ComponentTask`](crate::component::ComponentTask) re-renders on the next
+/// poll without the component's `update` needing to report a change — even
+/// if the write happened outside the component's own message channel (say,
+/// from a `glib::timeout_add` callback).
+///
+/// Components that never call into `Signal` are unaffected: `view()` runs
+/// under the usual `update`-driven `render` flag exactly as before.
+pub struct Signal<T> {
+    value: Rc<RefCell<T>>,
+    subscribers: Rc<RefCell<HashSet<EffectId>>>,
+}
+
+impl<T> Clone for Signal<T> {
+    fn clone(&self) -> Self {
+        Signal {
+            value: self.value.clone(),
+            subscribers: self.subscribers.clone(),
+        }
+    }
+}
+
+impl<T: Clone> Signal<T> {
+    pub fn new(value: T) -> Self {
+        Signal {
+            value: Rc::new(RefCell::new(value)),
+            subscribers: Rc::new(RefCell::new(HashSet::new())),
+        }
+    }
+
+    /// Read the current value, subscribing the currently-tracking render
+    /// effect (if any) to future writes.
+    pub fn get(&self) -> T {
+        if let Some(effect) = current_effect() {
+            subscribe(effect, self.subscribers.clone());
+        }
+        self.value.borrow().clone()
+    }
+
+    /// Replace the value and mark every subscribed effect dirty.
+    pub fn set(&self, value: T) {
+        *self.value.borrow_mut() = value;
+        for effect in self.subscribers.borrow().iter() {
+            mark_dirty(*effect);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::task::{RawWaker, RawWakerVTable, Wake};
+
+    fn noop_waker() -> Waker {
+        fn clone(_: *const ()) -> RawWaker {
+            raw_waker()
+        }
+        fn noop(_: *const ()) {}
+        fn raw_waker() -> RawWaker {
+            static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+            RawWaker::new(std::ptr::null(), &VTABLE)
+        }
+        unsafe { Waker::from_raw(raw_waker()) }
+    }
+
+    #[test]
+    fn set_marks_tracking_effect_dirty() {
+        let signal = Signal::new(1);
+        let effect = EffectId::next();
+        track(effect, || signal.get());
+        assert!(!take_dirty(effect));
+        signal.set(2);
+        assert!(take_dirty(effect));
+        // Cleared by the previous take_dirty.
+        assert!(!take_dirty(effect));
+    }
+
+    #[test]
+    fn set_wakes_the_registered_waker() {
+        let signal = Signal::new(1);
+        let effect = EffectId::next();
+        track(effect, || signal.get());
+        struct Flag(AtomicBool);
+        impl Wake for Flag {
+            fn wake(self: Arc<Self>) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+        let flag = Arc::new(Flag(AtomicBool::new(false)));
+        set_waker(effect, Waker::from(flag.clone()));
+        signal.set(2);
+        assert!(flag.0.load(Ordering::SeqCst));
+        assert!(take_dirty(effect));
+    }
+
+    #[test]
+    fn multiple_effects_on_one_signal_are_all_marked_dirty() {
+        let signal = Signal::new(1);
+        let a = EffectId::next();
+        let b = EffectId::next();
+        track(a, || signal.get());
+        track(b, || signal.get());
+        signal.set(2);
+        assert!(take_dirty(a));
+        assert!(take_dirty(b));
+    }
+
+    #[test]
+    fn re_tracking_drops_stale_subscriptions() {
+        let signal = Signal::new(1);
+        let effect = EffectId::next();
+        track(effect, || signal.get());
+        // Re-run without reading `signal` this time.
+        track(effect, || {});
+        signal.set(2);
+        assert!(!take_dirty(effect));
+    }
+
+    #[test]
+    fn drop_effect_unsubscribes_and_clears_state() {
+        let signal = Signal::new(1);
+        let effect = EffectId::next();
+        track(effect, || signal.get());
+        set_waker(effect, noop_waker());
+        drop_effect(effect);
+        signal.set(2);
+        assert!(!take_dirty(effect));
+    }
+}
@@ -0,0 +1,33 @@
+use std::borrow::Cow;
+use std::pin::Pin;
+
+use glib::futures::{Stream, StreamExt};
+
+/// A long-lived external event source a [`Component`](crate::component::Component)
+/// wants to listen to for as long as it's mounted, declared from
+/// [`Component::subscriptions`](crate::component::Component::subscriptions).
+///
+/// `id` must be stable across renders: it's how
+/// [`ComponentTask`](crate::component::ComponentTask) tells an unchanged
+/// subscription apart from a new one (whose stream should be merged in) or a
+/// removed one (whose stream should be dropped, releasing whatever it reads
+/// from).
+pub struct Subscription<Msg> {
+    pub(crate) id: Cow<'static, str>,
+    pub(crate) stream: Pin<Box<dyn Stream<Item = Msg>>>,
+}
+
+impl<Msg: 'static> Subscription<Msg> {
+    /// Subscribe to `stream`, mapping each item it produces to a `Msg` with
+    /// `map`. `id` identifies this subscription across renders.
+    pub fn new<S, T, F>(id: impl Into<Cow<'static, str>>, stream: S, map: F) -> Self
+    where
+        S: 'static + Stream<Item = T>,
+        F: 'static + Fn(T) -> Msg,
+    {
+        Subscription {
+            id: id.into(),
+            stream: Box::pin(stream.map(map)),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use glib::futures::channel::oneshot;
+use glib::futures::FutureExt;
+
+use crate::component::Component;
+use crate::signal::{self, EffectId};
+use crate::vnode::VNode;
+
+/// A plain-data snapshot of a [`VNode`] tree: element type and children,
+/// with no live GTK widget behind it. Element properties are not captured,
+/// and the type implements no serialization trait.
+///
+/// Produced by [`render`] (and `ComponentTask::render_to_tree`) for
+/// headless unit tests of a component's `view()` output, where there's no
+/// X/Wayland server to build real widgets against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Tree {
+    Object {
+        type_name: &'static str,
+        children: Vec<Tree>,
+    },
+    Component {
+        type_name: &'static str,
+    },
+}
+
+impl<C: Component> From<&VNode<C>> for Tree {
+    fn from(node: &VNode<C>) -> Self {
+        match node {
+            VNode::Object { type_name, children } => Tree::Object {
+                type_name: *type_name,
+                children: children.iter().map(Tree::from).collect(),
+            },
+            VNode::Component { type_name } => Tree::Component { type_name: *type_name },
+        }
+    }
+}
+
+/// Drive `C::create`/`update`/`view` through `messages` with no GTK
+/// involved at all, returning the rendered [`Tree`] after the initial
+/// render and after each message that actually triggered one.
+///
+/// This calls `C`'s methods directly rather than going through
+/// [`ComponentTask`](crate::component::ComponentTask), since `process`
+/// builds and patches real widgets via [`crate::vdom::State`], which this
+/// tree has no display server to back; a frame is only pushed when `update`
+/// returns `true` or a read [`Signal`](crate::signal::Signal) is dirty, the
+/// same gating `process` applies to its own re-render, so this can't report
+/// a frame the real runtime would never have patched onto screen.
+///
+/// Runs on a throwaway `glib::MainContext` and delivers its result through
+/// a oneshot channel so it behaves like the real async task loop, without
+/// needing a running main loop or display server — call it straight from a
+/// `#[test]`.
+pub fn render<C: 'static + Component>(props: C::Properties, messages: Vec<C::Message>) -> Vec<Tree> {
+    let (send, recv) = oneshot::channel();
+    let context = glib::MainContext::new();
+    context.block_on(async move {
+        let mut state = C::create(props);
+        let effect = EffectId::next();
+        let mut frames = vec![Tree::from(&signal::track(effect, || state.view()))];
+        for msg in messages {
+            let render = state.update(msg);
+            if render || signal::take_dirty(effect) {
+                frames.push(Tree::from(&signal::track(effect, || state.view())));
+            }
+        }
+        signal::drop_effect(effect);
+        let _ = send.send(frames);
+    });
+    recv.now_or_never()
+        .expect("block_on returned before the render task completed")
+        .expect("render task dropped its result")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Counter {
+        count: u32,
+    }
+
+    impl Component for Counter {
+        type Message = i32;
+        type Properties = ();
+
+        fn update(&mut self, delta: i32) -> bool {
+            self.count = (self.count as i32 + delta) as u32;
+            true
+        }
+
+        fn view(&self) -> VNode<Self> {
+            if self.count == 0 {
+                VNode::Object {
+                    type_name: "zero",
+                    children: Vec::new(),
+                }
+            } else {
+                VNode::Object {
+                    type_name: "nonzero",
+                    children: Vec::new(),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn render_reflects_each_update() {
+        let frames = render::<Counter>((), vec![1, -1]);
+        assert_eq!(
+            frames,
+            vec![
+                Tree::Object {
+                    type_name: "zero",
+                    children: Vec::new(),
+                },
+                Tree::Object {
+                    type_name: "nonzero",
+                    children: Vec::new(),
+                },
+                Tree::Object {
+                    type_name: "zero",
+                    children: Vec::new(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,26 @@
+use gtk::{Container, Widget};
+
+use crate::component::Component;
+use crate::scope::Scope;
+use crate::vnode::VNode;
+
+/// The built widget tree for a mounted component, kept around so a later
+/// [`VNode`] can be diffed and patched against the live GTK widgets.
+pub struct State<C: Component> {
+    object: Widget,
+    _marker: std::marker::PhantomData<C>,
+}
+
+impl<C: Component> State<C> {
+    pub fn build(_view: &VNode<C>, _parent: Option<&Container>, _scope: &Scope<C>) -> Self {
+        unimplemented!("building the real widget tree is outside this snapshot")
+    }
+
+    pub fn patch(&mut self, _view: &VNode<C>, _parent: Option<&Container>, _scope: &Scope<C>) -> bool {
+        unimplemented!("patching the real widget tree is outside this snapshot")
+    }
+
+    pub fn object(&self) -> &Widget {
+        &self.object
+    }
+}
@@ -0,0 +1,16 @@
+use crate::component::Component;
+
+/// A node in the virtual tree produced by [`Component::view`](crate::component::Component::view).
+///
+/// This is a minimal placeholder for the real vgtk virtual DOM representation;
+/// it only needs to carry enough shape for [`crate::vdom::State`] to build and
+/// patch widgets against.
+pub enum VNode<C: Component> {
+    Object {
+        type_name: &'static str,
+        children: Vec<VNode<C>>,
+    },
+    Component {
+        type_name: &'static str,
+    },
+}